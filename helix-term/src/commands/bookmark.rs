@@ -0,0 +1,164 @@
+/// Command-layer wiring for [`helix_view::BookmarkStore`]: toggling a
+/// bookmark at the cursor, listing every bookmark in a [`Peek`] popup (reusing
+/// the same jump-on-`Enter` machinery as go-to-definition), and jumping to the
+/// next/previous bookmark in the current document.
+///
+/// NOTE: these are plain `fn(&mut Context)` commands, matching the
+/// `peek_*` commands in `commands::lsp`, but nothing here registers them as
+/// `MappableCommand`s or binds default keys -- that lives in the top-level
+/// `commands.rs`/`keymap/default.rs` static command tables (and the `mod
+/// bookmark;` declaration that would make this module reachable from there),
+/// none of which are part of this tree, so there's no default keybinding for
+/// any of them yet.
+use std::path::PathBuf;
+
+use helix_core::{BookmarkUri, Selection};
+use helix_lsp::{lsp, OffsetEncoding};
+use helix_view::{editor::Action, Align, Editor};
+
+use crate::{
+    compositor::Context,
+    ui::lsp::{Peek, PeekKind, PeekPopup, PeekTarget},
+};
+
+/// Toggles a bookmark at the current line of the focused document.
+pub fn bookmark_toggle(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let line = text.char_to_line(doc.selection(view.id).primary().cursor(text));
+
+    match helix_view::toggle_bookmark(&mut cx.editor.bookmarks, doc, line, String::new()) {
+        Ok(true) => cx.editor.set_status("bookmark added"),
+        Ok(false) => cx.editor.set_status("bookmark removed"),
+        Err(err) => cx
+            .editor
+            .set_error(format!("failed to toggle bookmark: {err}")),
+    }
+}
+
+/// Opens every bookmark, across every file, in a [`Peek`] popup -- pressing
+/// `Enter` on one jumps to it the same way go-to-definition does.
+pub fn bookmark_list(cx: &mut Context) {
+    let bookmarks: Vec<BookmarkUri> = cx
+        .editor
+        .bookmarks
+        .by_path()
+        .values()
+        .flatten()
+        .cloned()
+        .collect();
+
+    if bookmarks.is_empty() {
+        cx.editor.set_status("no bookmarks");
+        return;
+    }
+
+    let targets: Vec<PeekTarget> = bookmarks
+        .into_iter()
+        .filter_map(|bookmark| bookmark_target(cx.editor, bookmark))
+        .collect();
+
+    if targets.is_empty() {
+        cx.editor.set_error("no bookmarks found");
+        return;
+    }
+
+    let config_loader = cx.editor.syn_loader.clone();
+    let popup = PeekPopup::new(
+        Peek::ID,
+        Peek::new(targets, PeekKind::Bookmarks, config_loader),
+        0,
+        0,
+    );
+    cx.push_layer(Box::new(popup));
+}
+
+/// Jumps to the next bookmark (by line) after the cursor in the current
+/// document, wrapping around to the first one past the end.
+pub fn goto_next_bookmark(cx: &mut Context) {
+    goto_bookmark(cx, true);
+}
+
+/// Jumps to the previous bookmark (by line) before the cursor in the current
+/// document, wrapping around to the last one past the start.
+pub fn goto_prev_bookmark(cx: &mut Context) {
+    goto_bookmark(cx, false);
+}
+
+fn goto_bookmark(cx: &mut Context, forward: bool) {
+    let (view, doc) = current!(cx.editor);
+    let Some(path) = doc.path().cloned() else {
+        cx.editor
+            .set_error("cannot jump to a bookmark in a buffer that isn't backed by a file");
+        return;
+    };
+
+    let mut lines: Vec<usize> = cx
+        .editor
+        .bookmarks
+        .list(&path)
+        .into_iter()
+        .map(|bookmark| bookmark.line)
+        .collect();
+    if lines.is_empty() {
+        cx.editor.set_status("no bookmarks in this file");
+        return;
+    }
+    lines.sort_unstable();
+
+    let text = doc.text().slice(..);
+    let current_line = text.char_to_line(doc.selection(view.id).primary().cursor(text));
+    let target_line = if forward {
+        lines
+            .iter()
+            .find(|&&line| line > current_line)
+            .or(lines.first())
+    } else {
+        lines
+            .iter()
+            .rev()
+            .find(|&&line| line < current_line)
+            .or(lines.last())
+    };
+
+    let Some(&target_line) = target_line else {
+        return;
+    };
+
+    let pos = doc.text().line_to_char(target_line);
+    doc.set_selection(view.id, Selection::point(pos));
+    helix_view::align_view(doc, view, Align::Center);
+}
+
+/// Builds a [`PeekTarget`] showing the line a bookmark sits on, opening its
+/// document (without focusing it) if it isn't already.
+fn bookmark_target(editor: &mut Editor, bookmark: BookmarkUri) -> Option<PeekTarget> {
+    let path: PathBuf = bookmark.path.into();
+
+    let doc_id = match editor.document_by_path(&path) {
+        Some(doc) => doc.id(),
+        None => editor.open(&path, Action::Load).ok()?,
+    };
+    let doc = editor.document(doc_id)?;
+    let text = doc.text();
+    let line = bookmark.line.min(text.len_lines().saturating_sub(1));
+
+    let lines = vec![text.line(line).to_string()];
+    let language = doc.language_name().unwrap_or_default().to_string();
+    let file_path = path.to_string_lossy().to_string();
+
+    Some(PeekTarget {
+        // `character: 0` is valid under any offset encoding, so there's no
+        // need to know which one the (possibly nonexistent) language server
+        // for this file actually uses.
+        document_position: lsp::Position::new(line as u32, 0),
+        document_path: path,
+        offset_encoding: OffsetEncoding::Utf8,
+        lines,
+        language,
+        file_path,
+        hover: Some(lsp::HoverContents::Scalar(lsp::MarkedString::String(
+            bookmark.name,
+        ))),
+    })
+}
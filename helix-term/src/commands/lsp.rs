@@ -0,0 +1,292 @@
+/// `peek_*` commands gather the same LSP results as their `goto_*`
+/// counterparts, but open them in a [`Peek`] popup instead of jumping
+/// straight to the first (or only) one, so the caller can glance at -- and,
+/// via the peek's edit mode, quick-fix -- the result without leaving the
+/// current buffer. `jump_to_current`'s `Enter` binding is what performs the
+/// actual jump once the user picks a location.
+use std::sync::Arc;
+
+use futures_util::future::join_all;
+use helix_lsp::{lsp, OffsetEncoding};
+use helix_view::editor::Action;
+
+use crate::{
+    compositor::Context,
+    ui::lsp::{Peek, PeekKind, PeekPopup, PeekTarget},
+};
+
+pub fn peek_definition(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::GotoDefinition);
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view.id, offset_encoding);
+    let goto_future = language_server.goto_definition(doc.identifier(), pos, None);
+    let language_server = language_server.clone();
+
+    let future = async move {
+        let locations = flatten_goto_response(goto_future.await);
+        let hovers = fetch_hovers(language_server, &locations).await;
+        (locations, hovers)
+    };
+
+    cx.callback(
+        future,
+        move |editor, compositor, (locations, hovers): PeekResults| {
+            if locations.is_empty() {
+                editor.set_error("no definitions found");
+                return;
+            }
+            open_peek_popup(
+                editor,
+                compositor,
+                PeekKind::Definition,
+                locations,
+                hovers,
+                offset_encoding,
+            );
+        },
+    );
+}
+
+pub fn peek_declaration(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::GotoDeclaration);
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view.id, offset_encoding);
+    let goto_future = language_server.goto_declaration(doc.identifier(), pos, None);
+    let language_server = language_server.clone();
+
+    let future = async move {
+        let locations = flatten_goto_response(goto_future.await);
+        let hovers = fetch_hovers(language_server, &locations).await;
+        (locations, hovers)
+    };
+
+    cx.callback(
+        future,
+        move |editor, compositor, (locations, hovers): PeekResults| {
+            if locations.is_empty() {
+                editor.set_error("no definitions found");
+                return;
+            }
+            open_peek_popup(
+                editor,
+                compositor,
+                PeekKind::Declaration,
+                locations,
+                hovers,
+                offset_encoding,
+            );
+        },
+    );
+}
+
+pub fn peek_type_definition(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::GotoTypeDefinition);
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view.id, offset_encoding);
+    let goto_future = language_server.goto_type_definition(doc.identifier(), pos, None);
+    let language_server = language_server.clone();
+
+    let future = async move {
+        let locations = flatten_goto_response(goto_future.await);
+        let hovers = fetch_hovers(language_server, &locations).await;
+        (locations, hovers)
+    };
+
+    cx.callback(
+        future,
+        move |editor, compositor, (locations, hovers): PeekResults| {
+            if locations.is_empty() {
+                editor.set_error("no definitions found");
+                return;
+            }
+            open_peek_popup(
+                editor,
+                compositor,
+                PeekKind::TypeDefinition,
+                locations,
+                hovers,
+                offset_encoding,
+            );
+        },
+    );
+}
+
+pub fn peek_implementation(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::GotoImplementation);
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view.id, offset_encoding);
+    let goto_future = language_server.goto_implementation(doc.identifier(), pos, None);
+    let language_server = language_server.clone();
+
+    let future = async move {
+        let locations = flatten_goto_response(goto_future.await);
+        let hovers = fetch_hovers(language_server, &locations).await;
+        (locations, hovers)
+    };
+
+    cx.callback(
+        future,
+        move |editor, compositor, (locations, hovers): PeekResults| {
+            if locations.is_empty() {
+                editor.set_error("no definitions found");
+                return;
+            }
+            open_peek_popup(
+                editor,
+                compositor,
+                PeekKind::Implementation,
+                locations,
+                hovers,
+                offset_encoding,
+            );
+        },
+    );
+}
+
+pub fn peek_references(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let language_server =
+        language_server_with_feature!(cx.editor, doc, LanguageServerFeature::FindReferences);
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view.id, offset_encoding);
+    let references_future = language_server.text_document_references(doc.identifier(), pos, None);
+    let language_server = language_server.clone();
+
+    let future = async move {
+        let locations = references_future.await.unwrap_or_default();
+        let hovers = fetch_hovers(language_server, &locations).await;
+        (locations, hovers)
+    };
+
+    cx.callback(
+        future,
+        move |editor, compositor, (locations, hovers): PeekResults| {
+            if locations.is_empty() {
+                editor.set_error("no references found");
+                return;
+            }
+            open_peek_popup(
+                editor,
+                compositor,
+                PeekKind::References,
+                locations,
+                hovers,
+                offset_encoding,
+            );
+        },
+    );
+}
+
+/// `(location, hover docs for that location)` pairs, gathered together so a
+/// single `cx.callback` round trip can deliver both the navigation result
+/// and its docs to the popup at once.
+type PeekResults = (Vec<lsp::Location>, Vec<Option<lsp::HoverContents>>);
+
+/// Flattens a `GotoDefinitionResponse`-shaped result into a plain list of
+/// locations, shared by every `goto_*`-style `peek_*` command above
+/// (`peek_references` already gets a flat list from the server).
+fn flatten_goto_response(response: Option<lsp::GotoDefinitionResponse>) -> Vec<lsp::Location> {
+    match response {
+        Some(lsp::GotoDefinitionResponse::Scalar(location)) => vec![location],
+        Some(lsp::GotoDefinitionResponse::Array(locations)) => locations,
+        Some(lsp::GotoDefinitionResponse::Link(links)) => links
+            .into_iter()
+            .map(|link| lsp::Location::new(link.target_uri, link.target_range))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Requests `textDocument/hover` for every one of `locations` in parallel,
+/// using `language_server` directly (by URI, without needing the location's
+/// document open) rather than failing the whole peek if a single hover
+/// request is slow or unsupported.
+async fn fetch_hovers(
+    language_server: Arc<helix_lsp::Client>,
+    locations: &[lsp::Location],
+) -> Vec<Option<lsp::HoverContents>> {
+    let requests = locations.iter().map(|location| {
+        let identifier = lsp::TextDocumentIdentifier::new(location.uri.clone());
+        language_server.text_document_hover(identifier, location.range.start, None)
+    });
+
+    join_all(requests)
+        .await
+        .into_iter()
+        .map(|hover: Option<lsp::Hover>| hover.map(|hover| hover.contents))
+        .collect()
+}
+
+/// Builds a [`Peek`] from `locations` (paired with their already-fetched
+/// `hovers`) and pushes it onto the compositor.
+fn open_peek_popup(
+    editor: &mut helix_view::Editor,
+    compositor: &mut crate::compositor::Compositor,
+    kind: PeekKind,
+    locations: Vec<lsp::Location>,
+    hovers: Vec<Option<lsp::HoverContents>>,
+    offset_encoding: OffsetEncoding,
+) {
+    let targets: Vec<PeekTarget> = locations
+        .into_iter()
+        .zip(hovers)
+        .filter_map(|(location, hover)| peek_target(editor, location, hover, offset_encoding))
+        .collect();
+
+    if targets.is_empty() {
+        editor.set_error("no locations found");
+        return;
+    }
+
+    let config_loader = editor.syn_loader.clone();
+    let popup = PeekPopup::new(Peek::ID, Peek::new(targets, kind, config_loader), 0, 0);
+    compositor.push(Box::new(popup));
+}
+
+/// Builds a [`PeekTarget`] by reading the lines spanning `location.range` out
+/// of its document, opening the document (without focusing it) if it isn't
+/// already.
+fn peek_target(
+    editor: &mut helix_view::Editor,
+    location: lsp::Location,
+    hover: Option<lsp::HoverContents>,
+    offset_encoding: OffsetEncoding,
+) -> Option<PeekTarget> {
+    let path = location.uri.to_file_path().ok()?;
+
+    let doc_id = match editor.document_by_path(&path) {
+        Some(doc) => doc.id(),
+        None => editor.open(&path, Action::Load).ok()?,
+    };
+    let doc = editor.document(doc_id)?;
+    let text = doc.text();
+
+    let start = helix_lsp::util::lsp_pos_to_pos(text, location.range.start, offset_encoding)?;
+    let end = helix_lsp::util::lsp_pos_to_pos(text, location.range.end, offset_encoding)?;
+    let start_line = text.char_to_line(start);
+    let end_line = text.char_to_line(end);
+
+    let lines = (start_line..=end_line)
+        .map(|line| text.line(line).to_string())
+        .collect();
+    let language = doc.language_name().unwrap_or_default().to_string();
+    let file_path = path.to_string_lossy().to_string();
+
+    Some(PeekTarget {
+        document_position: location.range.start,
+        document_path: path,
+        offset_encoding,
+        lines,
+        language,
+        file_path,
+        hover,
+    })
+}
@@ -0,0 +1,192 @@
+use tui::{
+    buffer::Buffer as Surface,
+    text::{Span, Spans, Text},
+    widgets::{Paragraph, Widget},
+};
+
+use helix_view::{
+    graphics::Rect,
+    input::{Event, MouseButton, MouseEventKind},
+    Editor,
+};
+
+use crate::{
+    compositor::{Callback, Component, Context, EventResult},
+    key,
+    ui::{lsp::PeekPopup, spinner::ProgressSpinners},
+};
+
+/// A single statusline item that aggregates every active language server's
+/// progress into one line, instead of one bare spinner per server.
+///
+/// Clicking it (or pressing enter while it's focused) opens a popup listing
+/// every in-flight task, grouped by server.
+#[derive(Default)]
+pub struct ActivityIndicator {
+    /// The area `render` last painted the indicator into, used to hit-test
+    /// clicks against in `handle_event`.
+    area: Rect,
+}
+
+impl ActivityIndicator {
+    pub const ID: &'static str = "activity-indicator";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The server (by name) and task whose progress was most recently
+    /// updated, used as the one line shown in the statusline.
+    fn most_recent<'a>(
+        spinners: &'a ProgressSpinners,
+        editor: &'a Editor,
+    ) -> Option<(&'a str, &'a crate::ui::spinner::Progress)> {
+        spinners
+            .iter()
+            .filter_map(|(id, spinner)| Some((id, spinner, spinner.progress()?)))
+            .filter(|(_, spinner, _)| !spinner.is_stopped())
+            .max_by_key(|(_, spinner, _)| spinner.last_update())
+            .map(|(id, _, progress)| {
+                let name = editor
+                    .language_servers
+                    .get_by_id(id)
+                    .map(|client| client.name())
+                    .unwrap_or("language server");
+                (name, progress)
+            })
+    }
+
+    /// Every in-flight task, grouped by server name, for the detail popup.
+    pub fn tasks<'a>(
+        spinners: &'a ProgressSpinners,
+        editor: &'a Editor,
+    ) -> Vec<(&'a str, &'a crate::ui::spinner::Progress)> {
+        spinners
+            .iter()
+            .filter(|(_, spinner)| !spinner.is_stopped())
+            .filter_map(|(id, spinner)| Some((id, spinner.progress()?)))
+            .map(|(id, progress)| {
+                let name = editor
+                    .language_servers
+                    .get_by_id(id)
+                    .map(|client| client.name())
+                    .unwrap_or("language server");
+                (name, progress)
+            })
+            .collect()
+    }
+}
+
+impl Component for ActivityIndicator {
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        self.area = area;
+
+        let editor = &*cx.editor;
+        let spinners = editor.language_servers.progress();
+        let Some((name, progress)) = Self::most_recent(spinners, editor) else {
+            return;
+        };
+        let name = name.to_owned();
+        let title = progress.title.clone();
+        let message = progress.message.clone();
+        let percentage = progress.percentage;
+
+        let frame = cx.editor.language_servers.progress_mut().current_frame();
+        let mut line = vec![Span::raw(format!("{frame} {name}: {title}"))];
+        if let Some(message) = &message {
+            line.push(Span::raw(format!(" {message}")));
+        }
+        if let Some(percentage) = percentage {
+            line.push(Span::raw(format!(" {percentage}%")));
+        }
+
+        Paragraph::new(Spans::from(line)).render(area, surface);
+    }
+
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let clicked = match event {
+            Event::Mouse(mouse) => {
+                let within = mouse.column >= self.area.left()
+                    && mouse.column < self.area.right()
+                    && mouse.row >= self.area.top()
+                    && mouse.row < self.area.bottom();
+                within && matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left))
+            }
+            Event::Key(key) => *key == key!(Enter),
+            _ => false,
+        };
+
+        if !clicked {
+            return EventResult::Ignored(None);
+        }
+
+        let lines: Vec<String> = Self::tasks(cx.editor.language_servers.progress(), cx.editor)
+            .into_iter()
+            .map(|(name, progress)| {
+                let mut line = format!("{name}: {}", progress.title);
+                if let Some(message) = &progress.message {
+                    line.push_str(&format!(" {message}"));
+                }
+                if let Some(percentage) = progress.percentage {
+                    line.push_str(&format!(" {percentage}%"));
+                }
+                line
+            })
+            .collect();
+
+        if lines.is_empty() {
+            return EventResult::Ignored(None);
+        }
+
+        let callback: Callback = Box::new(move |compositor, _| {
+            let details = ActivityDetails { lines };
+            // `0` here is just a seed -- PeekPopup recomputes its real
+            // content line count from `ActivityDetails::required_size` on
+            // the first render, so scrolling past a long task list works.
+            let popup = PeekPopup::new("activity-details", details, 0, 0).auto_close(true);
+            compositor.push(Box::new(popup));
+        });
+
+        EventResult::Consumed(Some(callback))
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(Self::ID)
+    }
+
+    fn required_size(&mut self, viewport: (u16, u16)) -> Option<(u16, u16)> {
+        Some((viewport.0, 1))
+    }
+}
+
+/// A read-only listing of every in-flight task, one per line, shown in the
+/// popup that opens when the [`ActivityIndicator`] is clicked.
+struct ActivityDetails {
+    lines: Vec<String>,
+}
+
+impl Component for ActivityDetails {
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let text = Text::from(self.lines.join("\n"));
+        let background = cx.editor.theme.get("ui.popup");
+        surface.clear_with(area, background);
+        Paragraph::new(&text)
+            .scroll((cx.scroll.unwrap_or_default() as u16, 0))
+            .render(area, surface);
+    }
+
+    fn handle_event(&mut self, _event: &Event, _cx: &mut Context) -> EventResult {
+        EventResult::Ignored(None)
+    }
+
+    fn required_size(&mut self, viewport: (u16, u16)) -> Option<(u16, u16)> {
+        let width = self
+            .lines
+            .iter()
+            .map(|line| line.chars().count() as u16)
+            .max()
+            .unwrap_or(0);
+        let height = self.lines.len() as u16;
+        Some((width.min(viewport.0), height.max(1)))
+    }
+}
@@ -2,64 +2,398 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::compositor::{Component, Context, EventResult};
-use crate::key;
 use crate::ui::Markdown;
+use crate::{ctrl, key};
 use arc_swap::ArcSwap;
-use helix_core::syntax;
+use helix_core::{syntax, Transaction};
 use helix_lsp::lsp;
 use helix_view::editor::Action;
-use helix_view::graphics::{Margin, Rect};
-use helix_view::input::Event;
+use helix_view::graphics::{CursorKind, Margin, Rect};
+use helix_view::input::{Event, KeyCode, KeyEvent};
+use helix_view::Editor;
 use tokio::time::Instant;
 use tui::buffer::Buffer;
 use tui::text::{Span, Text};
 use tui::widgets::{Paragraph, Widget, Wrap};
 
-pub struct PeekDefinition {
+/// One candidate location a [`Peek`] popup can show, e.g. one of several
+/// results for go-to-definition/references/implementations.
+pub struct PeekTarget {
+    pub document_position: lsp::Position,
+    pub document_path: PathBuf,
+    pub offset_encoding: helix_lsp::OffsetEncoding,
+    pub lines: Vec<String>,
+    pub language: String,
+    pub file_path: String,
+    /// The `textDocument/hover` response for this target, if one was
+    /// requested, rendered below the source snippet.
+    pub hover: Option<lsp::HoverContents>,
+}
+
+struct PeekEntry {
     document_position: lsp::Position,
     document_path: PathBuf,
     offset_encoding: helix_lsp::OffsetEncoding,
     markdown_content: Markdown,
+    hover_content: Option<Markdown>,
     file_path: String,
+    /// The snippet's language, used to re-fence `lines` as a code block when
+    /// rebuilding `markdown_content` after an edit is saved.
+    language: String,
+    /// Editable copy of the snippet, used as the backing buffer while the
+    /// entry is in edit mode; written back to `document_path` on save.
+    lines: Vec<String>,
+    /// Number of lines `lines` held when the entry was created (or last
+    /// saved), i.e. the size of the span in the target document that the
+    /// next save overwrites.
+    original_line_count: usize,
+}
+
+fn marked_string_to_markdown(marked: lsp::MarkedString) -> String {
+    match marked {
+        lsp::MarkedString::String(contents) => contents,
+        lsp::MarkedString::LanguageString(lsp::LanguageString { language, value }) => {
+            format!("```{language}\n{value}\n```")
+        }
+    }
 }
 
-impl PeekDefinition {
-    pub const ID: &'static str = "peek-definition";
+/// Joins a hover response into a single markdown document: each code block
+/// is fenced, each plain-text block is left as-is.
+fn hover_to_markdown(hover: lsp::HoverContents) -> String {
+    match hover {
+        lsp::HoverContents::Scalar(marked) => marked_string_to_markdown(marked),
+        lsp::HoverContents::Array(blocks) => blocks
+            .into_iter()
+            .map(marked_string_to_markdown)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        lsp::HoverContents::Markup(content) => content.value,
+    }
+}
+
+/// Which request produced a [`Peek`] popup, used to label the header. Jumping
+/// to a location works the same way for every kind. Most variants are LSP
+/// navigation requests; `Bookmarks` lists named bookmarks instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeekKind {
+    Definition,
+    Declaration,
+    TypeDefinition,
+    Implementation,
+    References,
+    Bookmarks,
+}
+
+impl PeekKind {
+    fn label(self) -> &'static str {
+        match self {
+            PeekKind::Definition => "Definition",
+            PeekKind::Declaration => "Declaration",
+            PeekKind::TypeDefinition => "Type Definition",
+            PeekKind::Implementation => "Implementation",
+            PeekKind::References => "References",
+            PeekKind::Bookmarks => "Bookmarks",
+        }
+    }
+}
+
+/// The result of the last [`Peek::parsed`] call, reused across frames as
+/// long as the selected entry is unchanged. A themed parse also serves
+/// unthemed requests, since styling doesn't change the text's size.
+struct ParsedCache {
+    index: usize,
+    with_theme: bool,
+    source: Text,
+    hover: Option<Text>,
+}
+
+/// Peeks at one or more LSP-reported locations without leaving the current
+/// buffer, cycling between them with `Ctrl-n`/`Ctrl-p` (or `Tab`/`Shift-Tab`)
+/// when more than one is available. Used for go-to-definition as well as
+/// references, implementations, and type-definition lookups -- `kind` only
+/// changes the header label; `Enter` jumps to the shown location the same
+/// way for every kind.
+pub struct Peek {
+    entries: Vec<PeekEntry>,
+    current: usize,
+    kind: PeekKind,
+    config_loader: Arc<ArcSwap<syntax::Loader>>,
+    cache: Option<ParsedCache>,
+    /// Whether the current entry's snippet is being edited in place, as a
+    /// quick-fix textarea, rather than shown as read-only rendered source.
+    editing: bool,
+    /// Cursor position (row, column, in chars) within the editing entry's
+    /// `lines`, only meaningful while `editing` is set.
+    cursor: (usize, usize),
+}
+
+impl Peek {
+    pub const ID: &'static str = "peek";
 
-    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        document_position: lsp::Position,
-        document_path: PathBuf,
-        offset_encoding: helix_lsp::OffsetEncoding,
-        lines: Vec<String>,
-        language: String,
-        file_path: String,
+        targets: Vec<PeekTarget>,
+        kind: PeekKind,
         config_loader: Arc<ArcSwap<syntax::Loader>>,
     ) -> Self {
-        let markdown = Markdown::new(
-            format!("```{}\n{}```", language, lines.join("")),
+        let entries = targets
+            .into_iter()
+            .map(|target| {
+                let markdown_content = Markdown::new(
+                    format!("```{}\n{}```", target.language, target.lines.join("")),
+                    config_loader.clone(),
+                );
+                let hover_content = target
+                    .hover
+                    .map(|hover| Markdown::new(hover_to_markdown(hover), config_loader.clone()));
+                let original_line_count = target.lines.len();
+
+                PeekEntry {
+                    document_position: target.document_position,
+                    document_path: target.document_path,
+                    offset_encoding: target.offset_encoding,
+                    markdown_content,
+                    hover_content,
+                    file_path: target.file_path,
+                    language: target.language,
+                    lines: target.lines,
+                    original_line_count,
+                }
+            })
+            .collect();
+
+        Self {
+            entries,
+            current: 0,
+            kind,
             config_loader,
+            cache: None,
+            editing: false,
+            cursor: (0, 0),
+        }
+    }
+
+    /// Parses the current entry's source (and hover docs, if any), reusing
+    /// the cached result unless the selected entry changed or a themed parse
+    /// is wanted and only an unthemed one is cached -- `render`/`required_size`
+    /// would otherwise reparse identical markdown on every single frame.
+    fn parsed(&mut self, theme: Option<&helix_view::theme::Theme>) -> (&Text, Option<&Text>) {
+        let with_theme = theme.is_some();
+        let stale = !matches!(
+            &self.cache,
+            Some(cache) if cache.index == self.current && (cache.with_theme || !with_theme)
         );
 
-        Self {
-            document_position,
-            document_path,
-            offset_encoding,
-            markdown_content: markdown,
-            file_path,
+        if stale {
+            let entry = &mut self.entries[self.current];
+            let source = entry.markdown_content.parse(theme);
+            let hover = entry.hover_content.as_mut().map(|hover| hover.parse(theme));
+            self.cache = Some(ParsedCache {
+                index: self.current,
+                with_theme,
+                source,
+                hover,
+            });
         }
+
+        let cache = self.cache.as_ref().expect("cache populated above");
+        (&cache.source, cache.hover.as_ref())
+    }
+
+    fn entry(&self) -> &PeekEntry {
+        &self.entries[self.current]
     }
 
-    pub fn jump_to_definition(&self, cx: &mut Context) {
-        let range = lsp::Range::new(self.document_position, self.document_position);
+    pub fn jump_to_current(&self, cx: &mut Context) {
+        let entry = self.entry();
+        let range = lsp::Range::new(entry.document_position, entry.document_position);
         crate::commands::lsp::jump_to_position(
             cx.editor,
-            &self.document_path,
+            &entry.document_path,
             range,
-            self.offset_encoding,
+            entry.offset_encoding,
             Action::Replace,
         );
     }
+
+    fn next(&mut self) {
+        if !self.entries.is_empty() {
+            self.current = (self.current + 1) % self.entries.len();
+        }
+    }
+
+    fn prev(&mut self) {
+        if !self.entries.is_empty() {
+            self.current = (self.current + self.entries.len() - 1) % self.entries.len();
+        }
+    }
+
+    /// Enters edit mode on the current entry's snippet, placing the cursor
+    /// at its start.
+    fn begin_edit(&mut self) {
+        self.editing = true;
+        self.cursor = (0, 0);
+    }
+
+    fn handle_edit_key(&mut self, event: KeyEvent, cx: &mut Context) {
+        match event {
+            key!(Esc) => self.editing = false,
+            ctrl!('s') => {
+                if let Err(err) = self.save_edit(cx) {
+                    cx.editor
+                        .set_error(format!("failed to save peek edit: {err}"));
+                }
+                self.editing = false;
+            }
+            key!(Enter) => self.insert_newline(),
+            key!(Backspace) => self.backspace(),
+            key!(Left) => self.move_cursor(-1, 0),
+            key!(Right) => self.move_cursor(1, 0),
+            key!(Up) => self.move_cursor(0, -1),
+            key!(Down) => self.move_cursor(0, 1),
+            KeyEvent {
+                code: KeyCode::Char(c),
+                ..
+            } => self.insert_char(c),
+            _ => {}
+        }
+    }
+
+    fn insert_char(&mut self, c: char) {
+        let (row, col) = self.cursor;
+        let line = &mut self.entries[self.current].lines[row];
+        let byte = line.char_indices().nth(col).map_or(line.len(), |(b, _)| b);
+        line.insert(byte, c);
+        self.cursor.1 += 1;
+    }
+
+    /// Splits the current line at the cursor, explicitly inserting the `\n`
+    /// that the split consumes -- every element of `lines` already carries
+    /// its own trailing newline, so without this the two resulting elements
+    /// would still concatenate back to the original text and the inserted
+    /// line break would be a no-op once saved.
+    fn insert_newline(&mut self) {
+        let (row, col) = self.cursor;
+        let entry = &mut self.entries[self.current];
+        let line = &mut entry.lines[row];
+        let byte = line.char_indices().nth(col).map_or(line.len(), |(b, _)| b);
+        let rest = line.split_off(byte);
+        line.push('\n');
+        entry.lines.insert(row + 1, rest);
+        self.cursor = (row + 1, 0);
+    }
+
+    fn backspace(&mut self) {
+        let (row, col) = self.cursor;
+        let entry = &mut self.entries[self.current];
+        if col > 0 {
+            let line = &mut entry.lines[row];
+            let byte = line.char_indices().nth(col - 1).map_or(0, |(b, _)| b);
+            line.remove(byte);
+            self.cursor.1 -= 1;
+        } else if row > 0 {
+            // Merging onto the previous line removes the newline between
+            // them, so strip its trailing `\n` before appending -- otherwise
+            // it would end up with one embedded in the middle of the line.
+            let current_line = entry.lines.remove(row);
+            let prev = &mut entry.lines[row - 1];
+            if prev.ends_with('\n') {
+                prev.pop();
+            }
+            let prev_len = prev.chars().count();
+            prev.push_str(&current_line);
+            self.cursor = (row - 1, prev_len);
+        }
+    }
+
+    fn move_cursor(&mut self, dx: isize, dy: isize) {
+        let entry = &self.entries[self.current];
+        let row = (self.cursor.0 as isize + dy)
+            .clamp(0, entry.lines.len().saturating_sub(1) as isize) as usize;
+        let line = &entry.lines[row];
+        // The trailing `\n` every line carries isn't a valid cursor column --
+        // landing on it would insert/backspace past the line break, silently
+        // migrating that edit onto the next logical line on save.
+        let max_col = line
+            .chars()
+            .count()
+            .saturating_sub(usize::from(line.ends_with('\n')));
+        let col = (self.cursor.1 as isize + dx).clamp(0, max_col as isize) as usize;
+        self.cursor = (row, col);
+    }
+
+    /// Translates the current entry's edited `lines` back into a transaction
+    /// against its `document_path`, replacing the snippet's original line
+    /// span. Opens the document (without focusing it) if it isn't already.
+    fn save_edit(&mut self, cx: &mut Context) -> anyhow::Result<()> {
+        let entry = &self.entries[self.current];
+        let path = entry.document_path.clone();
+
+        let current_view_id = cx.editor.tree.focus;
+        let doc_id = match cx.editor.document_by_path(&path) {
+            Some(doc) => doc.id(),
+            None => cx.editor.open(&path, Action::Load)?,
+        };
+
+        let entry = &self.entries[self.current];
+        let mut new_text = entry.lines.join("");
+        if !new_text.ends_with('\n') {
+            new_text.push('\n');
+        }
+        let original_line_count = entry.original_line_count;
+        let document_position = entry.document_position;
+        let offset_encoding = entry.offset_encoding;
+
+        let doc = cx
+            .editor
+            .document_mut(doc_id)
+            .expect("just looked up or opened above");
+
+        // `path` is usually a different document than the one the user is
+        // focused on (references/implementations/definitions elsewhere are
+        // the whole point of peeking), so it was very likely just opened via
+        // `Action::Load` with no selection for the focused view at all.
+        // Reuse the focused view's selection only if this document is
+        // actually displayed there; otherwise initialize a selection on a
+        // fresh, never-rendered view id the same way `apply_workspace_edit`
+        // does for documents that aren't open in any view.
+        let view_id = if doc.selections().contains_key(&current_view_id) {
+            current_view_id
+        } else {
+            let view_id = helix_view::ViewId::default();
+            doc.ensure_view_init(view_id);
+            view_id
+        };
+
+        let anchor =
+            helix_lsp::util::lsp_pos_to_pos(doc.text(), document_position, offset_encoding)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("snippet position is no longer valid in the target document")
+                })?;
+        let start_line = doc.text().char_to_line(anchor);
+        let from = doc.text().line_to_char(start_line);
+        let end_line = (start_line + original_line_count).min(doc.text().len_lines());
+        let to = doc.text().line_to_char(end_line);
+
+        let transaction = Transaction::change(
+            doc.text(),
+            std::iter::once((from, to, Some(new_text.into()))),
+        );
+        doc.apply(&transaction, view_id);
+
+        // The read-only view the user returns to after saving should reflect
+        // what was just written, not the pre-edit snippet, so rebuild the
+        // markdown from the edited lines and drop the stale parse cache.
+        let entry = &mut self.entries[self.current];
+        entry.markdown_content = Markdown::new(
+            format!("```{}\n{}```", entry.language, entry.lines.join("")),
+            self.config_loader.clone(),
+        );
+        entry.original_line_count = entry.lines.len();
+        self.cache = None;
+
+        Ok(())
+    }
 }
 
 // Constants for padding and layout, matching the hover component style
@@ -69,29 +403,98 @@ const PADDING_BOTTOM: u16 = 1;
 const HEADER_HEIGHT: u16 = 1;
 const SEPARATOR_HEIGHT: u16 = 1;
 
-impl Component for PeekDefinition {
+impl Component for Peek {
     fn render(&mut self, area: Rect, surface: &mut Buffer, cx: &mut Context) {
         let start = Instant::now();
 
         let margin = Margin::all(1);
         let inner_area = area.inner(margin);
 
-        // Create header
+        let total = self.entries.len();
+        let current = self.current;
+        let kind = self.kind;
+        let file_path = self.entry().file_path.clone();
+
+        // Create header, including the "(n/total)" counter once there's more
+        // than one candidate location to disambiguate between.
         let header_style = cx.editor.theme.get("ui.text.info");
-        let header = Text::from(Span::styled(&self.file_path, header_style));
+        let header_text = if total > 1 {
+            format!(
+                "{}: {} ({}/{})",
+                kind.label(),
+                file_path,
+                current + 1,
+                total
+            )
+        } else {
+            format!("{}: {}", kind.label(), file_path)
+        };
+        let header_text = if self.editing {
+            format!("{header_text} [editing, ^s save, Esc cancel]")
+        } else {
+            header_text
+        };
+        let header = Text::from(Span::styled(header_text, header_style));
         let header_para = Paragraph::new(&header);
         header_para.render(inner_area.with_height(HEADER_HEIGHT), surface);
 
         // Set up content area
         let content_area = inner_area.clip_top(HEADER_HEIGHT + SEPARATOR_HEIGHT);
 
-        // Parse and render the Markdown content
-        let contents = self.markdown_content.parse(Some(&cx.editor.theme));
+        if self.editing {
+            // Edit mode renders the live textarea buffer directly instead of
+            // the parsed/cached markdown, since it changes every keystroke.
+            let text = Text::from(self.entries[self.current].lines.join(""));
+            Paragraph::new(&text)
+                .wrap(Wrap { trim: false })
+                .render(content_area, surface);
 
-        let contents_para = Paragraph::new(&contents)
-            .wrap(Wrap { trim: false })
-            .scroll((cx.scroll.unwrap_or_default() as u16, 0));
-        contents_para.render(content_area, surface);
+            let duration = start.elapsed();
+            log::info!("PEEK PERF rendering: {:?}ms", duration.as_millis());
+            return;
+        }
+
+        // Parse the source snippet, and the hover docs, if there are any --
+        // cached, since this is called every frame.
+        let (contents, hover_contents) = self.parsed(Some(&cx.editor.theme));
+
+        match hover_contents {
+            Some(hover_contents) => {
+                let (_, source_height) =
+                    crate::ui::text::required_size(contents, content_area.width);
+                let source_height = source_height.min(content_area.height);
+
+                Paragraph::new(contents)
+                    .wrap(Wrap { trim: false })
+                    .scroll((cx.scroll.unwrap_or_default() as u16, 0))
+                    .render(content_area.with_height(source_height), surface);
+
+                let separator_area = content_area
+                    .clip_top(source_height)
+                    .with_height(SEPARATOR_HEIGHT);
+                let separator_style = cx
+                    .editor
+                    .theme
+                    .try_get("ui.virtual.ruler")
+                    .unwrap_or_else(|| cx.editor.theme.get("ui.text"));
+                let rule = Text::from(Span::styled(
+                    "─".repeat(separator_area.width as usize),
+                    separator_style,
+                ));
+                Paragraph::new(&rule).render(separator_area, surface);
+
+                let docs_area = content_area.clip_top(source_height + SEPARATOR_HEIGHT);
+                Paragraph::new(hover_contents)
+                    .wrap(Wrap { trim: false })
+                    .render(docs_area, surface);
+            }
+            None => {
+                Paragraph::new(contents)
+                    .wrap(Wrap { trim: false })
+                    .scroll((cx.scroll.unwrap_or_default() as u16, 0))
+                    .render(content_area, surface);
+            }
+        }
 
         let duration = start.elapsed();
         log::info!("PEEK PERF rendering: {:?}ms", duration.as_millis());
@@ -102,23 +505,61 @@ impl Component for PeekDefinition {
             return EventResult::Ignored(None);
         };
 
-        if let key!(Enter) = event {
-            self.jump_to_definition(cx);
+        if self.editing {
+            self.handle_edit_key(*event, cx);
+            return EventResult::Ignored(None);
         }
+
+        match *event {
+            key!(Enter) => self.jump_to_current(cx),
+            key!('i') => self.begin_edit(),
+            ctrl!('n') | key!(Tab) => self.next(),
+            ctrl!('p') | key!(BackTab) => self.prev(),
+            _ => {}
+        }
+
         EventResult::Ignored(None)
     }
 
+    fn cursor(&self, area: Rect, _editor: &Editor) -> (Option<helix_core::Position>, CursorKind) {
+        if !self.editing {
+            return (None, CursorKind::Hidden);
+        }
+
+        let margin = Margin::all(1);
+        let inner_area = area.inner(margin);
+        let content_area = inner_area.clip_top(HEADER_HEIGHT + SEPARATOR_HEIGHT);
+        let (row, col) = self.cursor;
+        let position =
+            helix_core::Position::new(content_area.y as usize + row, content_area.x as usize + col);
+        (Some(position), CursorKind::Block)
+    }
+
     fn id(&self) -> Option<&'static str> {
         Some(Self::ID)
     }
 
+    fn is_editing(&self) -> bool {
+        self.editing
+    }
+
     fn required_size(&mut self, viewport: (u16, u16)) -> Option<(u16, u16)> {
         let max_text_width = viewport.0.saturating_sub(PADDING_HORIZONTAL).clamp(10, 120);
 
-        // Parse the markdown content to calculate its size
-        let contents = self.markdown_content.parse(None);
-        let (content_width, content_height) =
-            crate::ui::text::required_size(&contents, max_text_width);
+        // Reuses the same cache `render` populates (its themed parse serves
+        // this unthemed request too), so neither call re-parses the other's
+        // work.
+        let (contents, hover_contents) = self.parsed(None);
+        let (mut content_width, mut content_height) =
+            crate::ui::text::required_size(contents, max_text_width);
+
+        // Account for the hover docs block, if there is one, below a separator.
+        if let Some(hover_contents) = hover_contents {
+            let (hover_width, hover_height) =
+                crate::ui::text::required_size(hover_contents, max_text_width);
+            content_width = content_width.max(hover_width);
+            content_height += SEPARATOR_HEIGHT + hover_height;
+        }
 
         // We always have a header with path
         let width = PADDING_HORIZONTAL + content_width;
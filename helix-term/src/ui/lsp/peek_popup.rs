@@ -8,6 +8,8 @@ use tui::{
     widgets::{Block, Widget},
 };
 
+use std::time::{Duration, Instant};
+
 use helix_core::Position;
 use helix_view::{
     graphics::{Margin, Rect},
@@ -19,42 +21,122 @@ const MIN_HEIGHT: u16 = 6;
 const MAX_HEIGHT: u16 = 26;
 const MAX_WIDTH: u16 = 120;
 
+/// How long the scrollbar stays visible after the last scroll action before
+/// it auto-hides.
+const SCROLLBAR_SHOW_INTERVAL: Duration = Duration::from_secs(1);
+
 struct RenderInfo {
     area: Rect,
     render_borders: bool,
     is_menu: bool,
 }
 
+/// Strategy used to bring a newly-selected row into view when the child's
+/// selection changes out from under us.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoscrollStrategy {
+    /// Scroll the minimal amount required to make the row visible.
+    Fit,
+    /// Center the row in the inner area.
+    Center,
+    /// Pin the row to the top of the inner area.
+    Top,
+}
+
+/// A request to scroll the popup's contents, resolved against the current
+/// inner height when applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAmount {
+    /// Scroll by a number of lines (negative scrolls up).
+    Line(isize),
+    /// Scroll by a number of half-pages (negative scrolls up).
+    HalfPage(isize),
+    /// Scroll by a number of full pages (negative scrolls up).
+    Page(isize),
+    /// Jump to the first line.
+    Top,
+    /// Jump to the last line.
+    Bottom,
+}
+
+/// Pins a specific content line to the top of the popup's inner area, so that
+/// resizing the terminal (or the child's content changing length) recomputes
+/// the scroll from a stable reference point instead of shifting whatever
+/// happens to be at the old numeric offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ScrollAnchor {
+    /// The content line currently pinned to the top of the inner area.
+    line: usize,
+}
+
+impl ScrollAnchor {
+    /// Returns the anchor that brings `selected` into view within an area of
+    /// `height` rows, per `strategy`.
+    fn autoscrolled_to(self, selected: usize, height: usize, strategy: AutoscrollStrategy) -> Self {
+        let line = match strategy {
+            AutoscrollStrategy::Top => selected,
+            AutoscrollStrategy::Center => selected.saturating_sub(height / 2),
+            AutoscrollStrategy::Fit if selected < self.line => selected,
+            AutoscrollStrategy::Fit if height > 0 && selected >= self.line + height => {
+                selected + 1 - height
+            }
+            AutoscrollStrategy::Fit => self.line,
+        };
+        Self { line }
+    }
+}
+
 pub struct PeekPopup<T: Component> {
     contents: T,
     position: Option<Position>,
     area: Rect,
     position_bias: Open,
+    /// The child's content height, used to clamp scrolling and to decide
+    /// whether the scrollbar should draw. Recomputed from `required_size`
+    /// every `render_info` call, so whatever is passed into `new` is only a
+    /// placeholder until the first render or layout.
     contents_line: usize,
     scroll_offset: usize,
-    scroll_half_pages: isize,
-    scroll_half_half_pages: isize,
+    last_inner_height: u16,
+    anchor: ScrollAnchor,
+    autoscroll_strategy: AutoscrollStrategy,
+    last_selection_index: Option<usize>,
+    last_scroll_was_manual: bool,
+    pending_g: bool,
     auto_close: bool,
     ignore_escape_key: bool,
     id: &'static str,
     has_scrollbar: bool,
+    always_show_scrollbar: bool,
+    last_scroll: Option<Instant>,
 }
 
 impl<T: Component> PeekPopup<T> {
+    /// `content_lines` only seeds the scroll clamp until the first
+    /// render/layout recomputes it from `contents.required_size`; pass `0`
+    /// unless `scroll_offset` also needs to start somewhere specific.
     pub fn new(id: &'static str, contents: T, content_lines: usize, scroll_offset: usize) -> Self {
         Self {
             contents,
             contents_line: content_lines,
             scroll_offset,
+            last_inner_height: 0,
             position: None,
             position_bias: Open::Below,
             area: Rect::new(0, 0, 0, 0),
-            scroll_half_pages: 0,
-            scroll_half_half_pages: 0,
+            anchor: ScrollAnchor {
+                line: scroll_offset,
+            },
+            autoscroll_strategy: AutoscrollStrategy::Fit,
+            last_selection_index: None,
+            last_scroll_was_manual: false,
+            pending_g: false,
             auto_close: false,
             ignore_escape_key: false,
             id,
             has_scrollbar: true,
+            always_show_scrollbar: false,
+            last_scroll: None,
         }
     }
 
@@ -97,20 +179,29 @@ impl<T: Component> PeekPopup<T> {
         self
     }
 
-    pub fn scroll_half_page_down(&mut self) {
-        self.scroll_half_pages += 1;
-    }
-
-    pub fn scroll_half_half_page_down(&mut self) {
-        self.scroll_half_half_pages += 1;
-    }
+    /// Scrolls the popup contents by `amount`, resolved against the current
+    /// inner height and clamped to the content's line count.
+    pub fn scroll(&mut self, amount: ScrollAmount) {
+        self.last_scroll_was_manual = true;
+        self.last_scroll = Some(Instant::now());
+        let height = self.last_inner_height.max(1) as isize;
+
+        let new_offset = match amount {
+            ScrollAmount::Line(n) => self.scroll_offset as isize + n,
+            ScrollAmount::HalfPage(n) => self.scroll_offset as isize + n * (height / 2).max(1),
+            ScrollAmount::Page(n) => self.scroll_offset as isize + n * height,
+            ScrollAmount::Top => 0,
+            ScrollAmount::Bottom => self.contents_line as isize,
+        };
 
-    pub fn scroll_half_page_up(&mut self) {
-        self.scroll_half_pages -= 1;
+        self.scroll_offset = new_offset.clamp(0, self.contents_line as isize) as usize;
+        self.anchor.line = self.scroll_offset;
     }
 
-    pub fn scroll_half_half_page_up(&mut self) {
-        self.scroll_half_half_pages -= 1;
+    /// Sets the strategy used to bring a newly-selected row into view.
+    pub fn autoscroll_strategy(mut self, strategy: AutoscrollStrategy) -> Self {
+        self.autoscroll_strategy = strategy;
+        self
     }
 
     /// Toggles the Popup's scrollbar.
@@ -121,6 +212,19 @@ impl<T: Component> PeekPopup<T> {
         self
     }
 
+    /// Keeps the scrollbar visible at all times instead of auto-hiding it
+    /// `SCROLLBAR_SHOW_INTERVAL` after the last scroll action.
+    pub fn always_show_scrollbar(mut self, always_show: bool) -> Self {
+        self.always_show_scrollbar = always_show;
+        self
+    }
+
+    /// Returns the instant at which the popup needs to be redrawn again even
+    /// without new input, e.g. to erase the scrollbar once it's timed out.
+    pub fn next_wake(&self) -> Option<Instant> {
+        self.last_scroll.map(|at| at + SCROLLBAR_SHOW_INTERVAL)
+    }
+
     pub fn contents(&self) -> &T {
         &self.contents
     }
@@ -194,6 +298,14 @@ impl<T: Component> PeekPopup<T> {
             .required_size((max_width, max_height))
             .expect("Component needs required_size implemented in order to be embedded in a popup");
 
+        // `required_size` reports the child's content height without
+        // clamping it to `max_height` (e.g. `Peek` ignores the height half of
+        // its `viewport` argument and only caps itself at 30 rows), so this
+        // is the range scrolling is clamped to -- recomputed every frame,
+        // this naturally tracks content changes like cycling entries or
+        // saving an edit.
+        self.contents_line = child_height as usize;
+
         width = width.min(MAX_WIDTH);
         let height = if render_borders {
             (child_height + 2).min(MAX_HEIGHT)
@@ -246,11 +358,11 @@ impl<T: Component> PeekPopup<T> {
 
         match kind {
             MouseEventKind::ScrollDown if self.has_scrollbar => {
-                self.scroll_half_page_down();
+                self.scroll(ScrollAmount::Line(3));
                 EventResult::Consumed(None)
             }
             MouseEventKind::ScrollUp if self.has_scrollbar => {
-                self.scroll_half_page_up();
+                self.scroll(ScrollAmount::Line(-3));
                 EventResult::Consumed(None)
             }
             _ => EventResult::Ignored(None),
@@ -279,29 +391,63 @@ impl<T: Component> Component for PeekPopup<T> {
             compositor.remove(self.id.as_ref());
         });
 
+        // `gg` (jump to top) is a two-key sequence; track whether the last
+        // key was a `g` so the next one can complete it.
+        let had_pending_g = std::mem::take(&mut self.pending_g);
+
         match key {
-            // esc or ctrl-c aborts the completion and closes the menu
-            key!(Esc) | ctrl!('c') => {
+            // esc or ctrl-c aborts the completion and closes the menu, unless
+            // the child is mid-edit, in which case it's the child's call
+            // whether this exits edit mode or does something else -- it
+            // shouldn't also tear down the whole popup underneath it.
+            key!(Esc) | ctrl!('c') if !self.contents.is_editing() => {
                 let _ = self.contents.handle_event(event, cx);
                 EventResult::Consumed(Some(close_fn))
             }
-            ctrl!('d') => {
-                self.scroll_half_page_down();
+            // These global scroll bindings only apply while the child isn't
+            // consuming raw keys itself (e.g. typing into an edit-mode
+            // textarea) -- otherwise typing a literal `g`/`G` or using
+            // ctrl-modified keys in the child's own bindings would be
+            // impossible.
+            ctrl!('d') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::HalfPage(1));
+                EventResult::Consumed(None)
+            }
+            ctrl!('u') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::HalfPage(-1));
+                EventResult::Consumed(None)
+            }
+            ctrl!('f') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::Page(1));
+                EventResult::Consumed(None)
+            }
+            ctrl!('b') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::Page(-1));
+                EventResult::Consumed(None)
+            }
+            ctrl!('e') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::Line(1));
                 EventResult::Consumed(None)
             }
-            ctrl!('u') => {
-                self.scroll_half_page_up();
+            ctrl!('y') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::Line(-1));
                 EventResult::Consumed(None)
             }
-            ctrl!('f') => {
-                self.scroll_half_half_page_up();
+            key!('g') if had_pending_g && !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::Top);
                 EventResult::Consumed(None)
             }
-            ctrl!('b') => {
-                self.scroll_half_half_page_down();
+            key!('g') if !self.contents.is_editing() => {
+                self.pending_g = true;
                 EventResult::Consumed(None)
             }
-            key!(Enter) => {
+            key!('G') if !self.contents.is_editing() => {
+                self.scroll(ScrollAmount::Bottom);
+                EventResult::Consumed(None)
+            }
+            // When the child is mid-edit, Enter inserts a newline in its
+            // textarea rather than confirming/closing the popup.
+            key!(Enter) if !self.contents.is_editing() => {
                 self.contents.handle_event(event, cx);
                 EventResult::Consumed(Some(close_fn))
             }
@@ -321,6 +467,20 @@ impl<T: Component> Component for PeekPopup<T> {
         // tab/enter/ctrl-k or whatever will confirm the selection/ ctrl-n/ctrl-p for scroll.
     }
 
+    /// Computes and stores `self.area` ahead of painting, so that mouse
+    /// events dispatched this frame are hit-tested against up-to-date
+    /// geometry instead of whatever `render` last left behind.
+    ///
+    /// NOTE: this only closes the staleness window if something calls it
+    /// before mouse events are dispatched each frame -- that caller is the
+    /// layer-stack layout pass in the compositor's event loop, which isn't
+    /// part of this component and hasn't been wired up yet. Until it is,
+    /// `self.area` is still only refreshed on the next `render`, same as
+    /// before this method existed.
+    fn layout(&mut self, viewport: Rect, cx: &mut Context) {
+        self.area = self.render_info(viewport, cx.editor).area;
+    }
+
     fn render(&mut self, viewport: Rect, surface: &mut Surface, cx: &mut Context) {
         let RenderInfo {
             area,
@@ -347,31 +507,39 @@ impl<T: Component> Component for PeekPopup<T> {
             Widget::render(Block::bordered(), area, surface);
         }
         let border = usize::from(render_borders);
-
-        let half_page_size = (inner.height / 2) as usize;
-        let half_half_page_size = (inner.height / 4) as usize;
-        let max_scroll = self.contents_line;
-
-        let scroll = max_scroll.min(
-            (self.scroll_half_pages * half_page_size as isize
-                + self.scroll_half_half_pages * half_half_page_size as isize
-                + self.scroll_offset as isize)
-                .max(0) as usize,
-        );
-        if half_page_size > 0 {
-            self.scroll_half_pages = (scroll / half_page_size) as isize;
-            self.scroll_half_half_pages =
-                ((scroll % half_page_size) / half_half_page_size) as isize;
-            self.scroll_offset = scroll
-                - (self.scroll_half_pages as usize * half_page_size
-                    + self.scroll_half_half_pages as usize * half_half_page_size);
+        self.last_inner_height = inner.height;
+
+        // If the child's selection moved since the last frame, bring it into
+        // view, unless the user has manually taken over scrolling -- in
+        // which case we leave the anchor alone so we don't fight them.
+        if let Some(selected) = self.contents.selection_index() {
+            if Some(selected) != self.last_selection_index {
+                self.last_selection_index = Some(selected);
+                if !self.last_scroll_was_manual {
+                    self.anchor = self.anchor.autoscrolled_to(
+                        selected,
+                        inner.height as usize,
+                        self.autoscroll_strategy,
+                    );
+                    self.scroll_offset = self.anchor.line.min(self.contents_line);
+                }
+            }
         }
 
+        let scroll = self.scroll_offset.min(self.contents_line);
+        self.scroll_offset = scroll;
+        self.anchor.line = scroll;
+
         cx.scroll = Some(scroll);
         self.contents.render(inner, surface, cx);
 
-        // render scrollbar if contents do not fit
-        if self.has_scrollbar {
+        // render scrollbar if contents do not fit, fading it out
+        // `SCROLLBAR_SHOW_INTERVAL` after the user last scrolled
+        let scrollbar_visible = self.always_show_scrollbar
+            || self
+                .last_scroll
+                .is_some_and(|at| at.elapsed() < SCROLLBAR_SHOW_INTERVAL);
+        if self.has_scrollbar && scrollbar_visible {
             let win_height = inner.height as usize;
             // let len = child_height as usize;
             let len = self.contents_line;
@@ -407,4 +575,10 @@ impl<T: Component> Component for PeekPopup<T> {
     fn id(&self) -> Option<&'static str> {
         Some(self.id)
     }
+
+    /// The compositor re-renders us at this instant even without new input,
+    /// so the auto-hidden scrollbar actually gets erased once it times out.
+    fn next_wake(&self) -> Option<Instant> {
+        PeekPopup::next_wake(self)
+    }
 }
@@ -30,6 +30,11 @@ impl ProgressSpinners {
         self.inner.values().any(|spinner| !spinner.is_stopped())
     }
 
+    /// Iterates over every tracked server's spinner, active or not.
+    pub fn iter(&self) -> impl Iterator<Item = (LanguageServerId, &Spinner)> {
+        self.inner.iter().map(|(id, spinner)| (*id, spinner))
+    }
+
     pub fn current_frame(&mut self) -> &str {
         let now = Instant::now();
 
@@ -52,14 +57,29 @@ pub fn any_spinner_active() -> bool {
     ACTIVE_SPINNER_COUNT.load(Ordering::Acquire) > 0
 }
 
+/// The most recently reported `$/progress` state for a single in-flight
+/// language server task.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    pub title: String,
+    pub message: Option<String>,
+    pub percentage: Option<u32>,
+}
+
 #[derive(Default, Debug)]
 pub struct Spinner {
     start: Option<Instant>,
+    progress: Option<Progress>,
+    last_update: Option<Instant>,
 }
 
 impl Spinner {
     pub fn new() -> Self {
-        Self { start: None }
+        Self {
+            start: None,
+            progress: None,
+            last_update: None,
+        }
     }
 
     pub fn start(&mut self) {
@@ -74,9 +94,31 @@ impl Spinner {
             ACTIVE_SPINNER_COUNT.fetch_sub(1, Ordering::Release);
         }
         self.start = None;
+        self.progress = None;
     }
 
     pub fn is_stopped(&self) -> bool {
         self.start.is_none()
     }
+
+    /// Records the latest `$/progress` title/message/percentage reported by
+    /// the server for the task driving this spinner.
+    pub fn set_progress(&mut self, title: String, message: Option<String>, percentage: Option<u32>) {
+        self.progress = Some(Progress {
+            title,
+            message,
+            percentage,
+        });
+        self.last_update = Some(Instant::now());
+    }
+
+    pub fn progress(&self) -> Option<&Progress> {
+        self.progress.as_ref()
+    }
+
+    /// When this spinner's progress was last updated, used to pick the
+    /// most-recently-active task when aggregating several servers.
+    pub fn last_update(&self) -> Option<Instant> {
+        self.last_update
+    }
 }
@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result};
+use helix_core::BookmarkUri;
+
+const BOOKMARKS_FILE: &str = ".bookmarks";
+
+/// Owns the in-memory bookmark table and keeps `.bookmarks` in sync with it.
+///
+/// Unlike the ad-hoc read path it replaces, loading tolerates a corrupt line
+/// (logging and skipping it) instead of panicking, and every mutation is
+/// persisted by writing a temp file and renaming it over `.bookmarks`, so a
+/// crash mid-write can't leave the file truncated or half-written.
+pub struct BookmarkStore {
+    /// Resolved once, at load time, so a later `:cd` can't make a mutation
+    /// persist to a different `.bookmarks` than the one we loaded from.
+    path: PathBuf,
+    by_path: HashMap<PathBuf, Vec<BookmarkUri>>,
+}
+
+impl Default for BookmarkStore {
+    fn default() -> Self {
+        Self {
+            path: Self::file_path(),
+            by_path: HashMap::new(),
+        }
+    }
+}
+
+impl BookmarkStore {
+    /// Loads bookmarks from `.bookmarks` in the current working directory.
+    pub fn load() -> Self {
+        Self::load_from(Self::file_path())
+    }
+
+    /// Loads bookmarks from `path`, tolerating (and skipping) corrupt lines.
+    fn load_from(path: PathBuf) -> Self {
+        let data = std::fs::read_to_string(&path).unwrap_or_default();
+
+        let mut by_path: HashMap<PathBuf, Vec<BookmarkUri>> = HashMap::new();
+        for line in data.lines().filter(|line| !line.is_empty()) {
+            match serde_json::from_str::<BookmarkUri>(line) {
+                Ok(bookmark) => by_path
+                    .entry(bookmark.path.clone().into())
+                    .or_default()
+                    .push(bookmark),
+                Err(err) => log::warn!("skipping corrupt bookmark line in {path:?}: {err}"),
+            }
+        }
+
+        Self { path, by_path }
+    }
+
+    fn file_path() -> PathBuf {
+        let mut path = helix_stdx::env::current_working_dir();
+        path.push(BOOKMARKS_FILE);
+        path
+    }
+
+    /// Bookmarks in `path`, in the order they were added.
+    pub fn list(&self, path: &Path) -> Vec<BookmarkUri> {
+        self.by_path.get(path).cloned().unwrap_or_default()
+    }
+
+    pub fn by_path(&self) -> &HashMap<PathBuf, Vec<BookmarkUri>> {
+        &self.by_path
+    }
+
+    /// Adds a bookmark at `path:line` if none exists there yet, or removes it
+    /// if one does. Returns whether a bookmark now exists at that line.
+    pub fn toggle(&mut self, path: &Path, line: usize, name: String) -> Result<bool> {
+        let bookmarks = self.by_path.entry(path.to_path_buf()).or_default();
+        let now_bookmarked = if let Some(pos) = bookmarks.iter().position(|b| b.line == line) {
+            bookmarks.remove(pos);
+            false
+        } else {
+            bookmarks.push(BookmarkUri {
+                path: path.to_string_lossy().to_string(),
+                line,
+                name,
+            });
+            true
+        };
+        self.persist()?;
+        Ok(now_bookmarked)
+    }
+
+    pub fn remove(&mut self, path: &Path, line: usize) -> Result<()> {
+        if let Some(bookmarks) = self.by_path.get_mut(path) {
+            bookmarks.retain(|b| b.line != line);
+        }
+        self.persist()
+    }
+
+    pub fn rename(&mut self, path: &Path, line: usize, name: String) -> Result<()> {
+        if let Some(bookmark) = self
+            .by_path
+            .get_mut(path)
+            .and_then(|bookmarks| bookmarks.iter_mut().find(|b| b.line == line))
+        {
+            bookmark.name = name;
+        }
+        self.persist()
+    }
+
+    /// Writes every bookmark back to `.bookmarks`, one JSON object per line,
+    /// via a temp-file-then-rename so a crash mid-write leaves the previous
+    /// contents on disk untouched.
+    fn persist(&self) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+
+        let mut data = String::new();
+        for bookmark in self.by_path.values().flatten() {
+            data.push_str(&serde_json::to_string(bookmark)?);
+            data.push('\n');
+        }
+
+        std::fs::write(&tmp_path, &data)
+            .with_context(|| format!("failed to write {tmp_path:?}"))?;
+        std::fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("failed to replace {:?}", self.path))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmarks_path(dir: &Path) -> PathBuf {
+        dir.join(BOOKMARKS_FILE)
+    }
+
+    #[test]
+    fn load_skips_corrupt_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = bookmarks_path(dir.path());
+        std::fs::write(
+            &path,
+            "not json\n{\"path\":\"a.rs\",\"line\":1,\"name\":\"a\"}\n",
+        )
+        .unwrap();
+
+        let store = BookmarkStore::load_from(path);
+
+        assert_eq!(store.list(Path::new("a.rs")).len(), 1);
+    }
+
+    #[test]
+    fn load_missing_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BookmarkStore::load_from(bookmarks_path(dir.path()));
+
+        assert!(store.by_path().is_empty());
+    }
+
+    #[test]
+    fn toggle_adds_then_removes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = BookmarkStore::load_from(bookmarks_path(dir.path()));
+        let path = Path::new("a.rs");
+
+        assert!(store.toggle(path, 5, "one".into()).unwrap());
+        assert_eq!(store.list(path).len(), 1);
+
+        assert!(!store.toggle(path, 5, "one".into()).unwrap());
+        assert!(store.list(path).is_empty());
+    }
+
+    #[test]
+    fn remove_and_rename() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = BookmarkStore::load_from(bookmarks_path(dir.path()));
+        let path = Path::new("a.rs");
+
+        store.toggle(path, 1, "one".into()).unwrap();
+        store.toggle(path, 2, "two".into()).unwrap();
+
+        store.rename(path, 1, "renamed".into()).unwrap();
+        assert_eq!(store.list(path)[0].name, "renamed");
+
+        store.remove(path, 2).unwrap();
+        assert_eq!(store.list(path).len(), 1);
+    }
+
+    #[test]
+    fn persist_round_trips_through_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let bookmarks_path = bookmarks_path(dir.path());
+        let mut store = BookmarkStore::load_from(bookmarks_path.clone());
+        let path = Path::new("a.rs");
+
+        store.toggle(path, 3, "three".into()).unwrap();
+
+        // The temp file used for the crash-safe write must not be left
+        // behind once the rename completes.
+        assert!(!bookmarks_path.with_extension("tmp").exists());
+
+        let reloaded = BookmarkStore::load_from(bookmarks_path);
+        assert_eq!(reloaded.list(path)[0].name, "three");
+    }
+}
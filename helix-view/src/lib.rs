@@ -3,6 +3,7 @@ pub mod macros;
 
 pub mod annotations;
 pub mod base64;
+pub mod bookmark;
 pub mod clipboard;
 pub mod document;
 pub mod editor;
@@ -73,6 +74,7 @@ pub fn align_view(doc: &mut Document, view: &View, align: Align) {
     doc.set_view_offset(view.id, view_offset);
 }
 
+pub use bookmark::BookmarkStore;
 pub use document::Document;
 pub use editor::Editor;
 use helix_core::{
@@ -84,35 +86,20 @@ pub use view::View;
 
 type BookmarkCache = RefCell<Option<HashMap<PathBuf, Vec<BookmarkUri>>>>;
 
+/// Reads `doc`'s bookmarks out of `store`, filling `bookmarks_cache` from the
+/// store's contents on first use.
+///
+/// `bookmarks_cache` is not refreshed when `store` is mutated:
+/// [`toggle_bookmark`] clears only `doc`'s per-document cache, so this returns
+/// the store's contents as of when `bookmarks_cache` was last filled, until
+/// the cache is cleared to `None`.
 pub fn read_and_update_bookmarks_cache(
     bookmarks_cache: &BookmarkCache,
+    store: &BookmarkStore,
     doc: &Document,
 ) -> Vec<BookmarkUri> {
-    let mut bookmark_file_path = helix_stdx::env::current_working_dir();
-    bookmark_file_path.push(".bookmarks");
-    let bookmark_file_path = bookmark_file_path.as_path().to_string_lossy().to_string();
-
     if bookmarks_cache.borrow().is_none() {
-        // read bookmarks from file and update the cache
-        log::info!("reading bookmark file from disk");
-
-        let bookmarks_data = std::fs::read_to_string(bookmark_file_path).unwrap_or("".into());
-        let bookmarks: Vec<BookmarkUri> = bookmarks_data
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|l| serde_json::from_str(l).unwrap())
-            .collect();
-
-        let mut new_bookmarks_cache: HashMap<PathBuf, Vec<BookmarkUri>> = HashMap::new();
-
-        for bookmark in bookmarks {
-            new_bookmarks_cache
-                .entry(bookmark.path.clone().into())
-                .and_modify(|b| b.push(bookmark.clone()))
-                .or_insert(vec![bookmark]);
-        }
-
-        *bookmarks_cache.borrow_mut() = Some(new_bookmarks_cache);
+        *bookmarks_cache.borrow_mut() = Some(store.by_path().clone());
     }
 
     let bookmarks = doc
@@ -127,47 +114,46 @@ pub fn read_and_update_bookmarks_cache(
     actualize_bookmarks(bookmarks)
 }
 
-pub fn read_and_update_document_bookmarks_cache(doc: &Document) -> Vec<BookmarkUri> {
-    let mut bookmark_file_path = helix_stdx::env::current_working_dir();
-    bookmark_file_path.push(".bookmarks");
-    let bookmark_file_path = bookmark_file_path.as_path().to_string_lossy().to_string();
+pub fn read_and_update_document_bookmarks_cache(
+    store: &BookmarkStore,
+    doc: &Document,
+) -> Vec<BookmarkUri> {
+    let Some(doc_path) = doc.path() else {
+        return vec![];
+    };
+
+    if doc.bookmarks_cache.borrow().is_none() {
+        let new_bookmarks_cache: HashMap<usize, BookmarkUri> = store
+            .list(doc_path)
+            .into_iter()
+            .map(|bookmark| (bookmark.line, bookmark))
+            .collect();
 
-    if let Some(doc_path) = doc
-        .path()
-        .map(|p| p.as_path().to_string_lossy().to_string())
-    {
-        if doc.bookmarks_cache.borrow().is_none() {
-            // read bookmarks from file and update the cache
-            log::info!("reading bookmark file from disk");
-
-            let bookmarks_data = std::fs::read_to_string(bookmark_file_path).unwrap_or("".into());
-            let bookmarks: Vec<BookmarkUri> = bookmarks_data
-                .lines()
-                .filter(|line| !line.is_empty())
-                .map(|l| serde_json::from_str(l).unwrap())
-                .collect();
-
-            let mut new_bookmarks_cache: HashMap<usize, BookmarkUri> = HashMap::new();
-
-            for bookmark in bookmarks {
-                if bookmark.path != doc_path {
-                    continue;
-                }
-
-                new_bookmarks_cache.insert(bookmark.line, bookmark);
-            }
-
-            *doc.bookmarks_cache.borrow_mut() = Some(new_bookmarks_cache);
-        }
-
-        doc.bookmarks_cache
-            .borrow()
-            .clone()
-            .map(|cache| cache.values().cloned().collect())
-            .unwrap_or_default()
-    } else {
-        vec![]
+        *doc.bookmarks_cache.borrow_mut() = Some(new_bookmarks_cache);
     }
+
+    doc.bookmarks_cache
+        .borrow()
+        .clone()
+        .map(|cache| cache.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Toggles the bookmark at `doc`'s current path and `line`, persisting the
+/// change and invalidating `doc`'s cache so the gutter reflects it on the
+/// very next render. Returns whether a bookmark now exists at that line.
+pub fn toggle_bookmark(
+    store: &mut BookmarkStore,
+    doc: &Document,
+    line: usize,
+    name: String,
+) -> anyhow::Result<bool> {
+    let path = doc
+        .path()
+        .ok_or_else(|| anyhow::anyhow!("cannot bookmark a buffer that isn't backed by a file"))?;
+    let now_bookmarked = store.toggle(path, line, name)?;
+    *doc.bookmarks_cache.borrow_mut() = None;
+    Ok(now_bookmarked)
 }
 
 pub fn convert_bookmarks_to_fake_diagnostics(